@@ -0,0 +1,9 @@
+//! Generated gRPC types and service definitions for the `centrium` package.
+
+tonic::include_proto!("centrium");
+
+/// Encoded `FileDescriptorSet` for the `centrium` package, embedded at
+/// build time by `build.rs`. Used to power server reflection so tools
+/// like `grpcurl` can introspect the API without a checked-in `.proto`.
+pub const FILE_DESCRIPTOR_SET: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/centrium_descriptor.bin"));
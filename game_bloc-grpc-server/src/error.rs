@@ -0,0 +1,35 @@
+use std::fmt;
+
+/// Error type returned by `Repository` implementations, independent of
+/// the backing store (MongoDB, in-memory, ...).
+#[derive(Debug)]
+pub enum RepoError {
+    NotFound,
+    Backend(String),
+}
+
+impl fmt::Display for RepoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepoError::NotFound => write!(f, "document not found"),
+            RepoError::Backend(message) => write!(f, "repository backend error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for RepoError {}
+
+impl From<mongodb::error::Error> for RepoError {
+    fn from(err: mongodb::error::Error) -> Self {
+        RepoError::Backend(err.to_string())
+    }
+}
+
+impl From<RepoError> for tonic::Status {
+    fn from(err: RepoError) -> Self {
+        match err {
+            RepoError::NotFound => tonic::Status::not_found(err.to_string()),
+            RepoError::Backend(message) => tonic::Status::internal(message),
+        }
+    }
+}
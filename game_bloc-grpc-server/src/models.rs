@@ -0,0 +1,112 @@
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+/// Implemented by every document type stored through a `Repository`,
+/// so `insert_one` can assign the generated id the same way whether
+/// the backend is MongoDB or the in-memory test double.
+pub trait Identifiable {
+    fn id(&self) -> Option<ObjectId>;
+    fn set_id(&mut self, id: ObjectId);
+}
+
+macro_rules! impl_identifiable {
+    ($ty:ty) => {
+        impl Identifiable for $ty {
+            fn id(&self) -> Option<ObjectId> {
+                self.id
+            }
+
+            fn set_id(&mut self, id: ObjectId) {
+                self.id = Some(id);
+            }
+        }
+    };
+}
+
+/// Document shape backing `profile_store`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileDocument {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub owner_id: String,
+    pub display_name: String,
+}
+
+/// Document shape backing `tournament_store`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TournamentDocument {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub name: String,
+    pub starts_at: bson::DateTime,
+}
+
+/// Document shape shared by `squad_store`, `games_store`,
+/// `notification_store`, `message_store`, `daily_reward_store` and
+/// `referral_map`. All of these are keyed by the owning player.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Owner {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub owner_id: String,
+    pub payload: bson::Document,
+}
+
+/// Persisted MongoDB change-stream resume token for a subscribing
+/// owner, keyed by `owner_id` so a reconnecting client resumes without
+/// gaps instead of replaying or missing events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationCursor {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub owner_id: String,
+    pub resume_token: bson::Document,
+}
+
+impl_identifiable!(ProfileDocument);
+impl_identifiable!(TournamentDocument);
+impl_identifiable!(Owner);
+impl_identifiable!(NotificationCursor);
+
+/// A client application allowed to request authorization codes, and the
+/// scopes it's allowed to request. Looked up by `client_id` (not `_id`)
+/// when `AuthState::authorize` decides whether to honor a request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisteredClient {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub client_id: String,
+    pub allowed_scopes: Vec<String>,
+}
+
+impl_identifiable!(RegisteredClient);
+
+/// A pending PKCE authorization, looked up by `code` (not `_id`) at
+/// exchange time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthGrant {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub code: String,
+    pub client_id: String,
+    pub scopes: Vec<String>,
+    pub code_challenge: String,
+    pub code_challenge_method: String,
+    pub consumed: bool,
+}
+
+impl_identifiable!(AuthGrant);
+
+/// An issued bearer token, persisted so the in-memory verification
+/// cache can be rehydrated on restart. Only a SHA-256 hash of the
+/// token is stored, never the token itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessTokenRecord {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub token_hash: String,
+    pub client_id: String,
+    pub scopes: Vec<String>,
+}
+
+impl_identifiable!(AccessTokenRecord);
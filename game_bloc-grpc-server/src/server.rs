@@ -0,0 +1,47 @@
+use std::error::Error;
+use std::net::SocketAddr;
+
+use tonic::transport::Server;
+
+use centrium_grpc_server::auth_service_server::AuthServiceServer;
+use centrium_grpc_server::game_bloc_service_server::GameBlocServiceServer;
+
+use crate::services::auth::ScopeInterceptor;
+use crate::services::db::GameBlocServiceState;
+use crate::services::health;
+
+/// Boots `GameBlocService` (behind `ScopeInterceptor`) and the public
+/// `AuthService` that issues its bearer tokens, together with the
+/// cross-cutting gRPC services (reflection, health) that ride
+/// alongside them.
+pub async fn serve(addr: SocketAddr, state: GameBlocServiceState) -> Result<(), Box<dyn Error>> {
+    state.reindex_search().await?;
+    state.auth.load_cache().await?;
+
+    let reflection_service = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(centrium_grpc_server::FILE_DESCRIPTOR_SET)
+        .build()?;
+
+    let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
+    for name in ["", health::GAME_BLOC_SERVICE_NAME, health::AUTH_SERVICE_NAME] {
+        health_reporter
+            .set_service_status(name, tonic_health::ServingStatus::Serving)
+            .await;
+    }
+
+    tokio::spawn(health::monitor(health_reporter, state.clone()));
+
+    let auth_service = AuthServiceServer::new(state.auth.clone());
+    let interceptor = ScopeInterceptor::new(state.auth.cache());
+    let game_bloc_service = GameBlocServiceServer::with_interceptor(state, interceptor);
+
+    Server::builder()
+        .add_service(reflection_service)
+        .add_service(health_service)
+        .add_service(auth_service)
+        .add_service(game_bloc_service)
+        .serve(addr)
+        .await?;
+
+    Ok(())
+}
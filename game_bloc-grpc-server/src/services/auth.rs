@@ -0,0 +1,446 @@
+//! PKCE (RFC 7636) token issuance and per-RPC scope enforcement.
+//!
+//! `AuthServiceImpl` issues tokens; `ScopeInterceptor` (wrapped around
+//! `GameBlocServiceServer`) verifies the bearer token on every call and
+//! attaches its granted `Scopes` to the request's extensions, so each
+//! handler can check the specific scope it needs with `require_scope`.
+//!
+//! PKCE alone only proves the caller holds both halves of a
+//! challenge/verifier pair it generated itself — it says nothing about
+//! which scopes that caller is allowed to hold. Since `AuthService` is
+//! deliberately reachable without a bearer token (it's what issues
+//! one), `authorize` additionally checks the requested scopes against
+//! `registered_clients`, a `client_id -> allowed_scopes` allow-list.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use mongodb::bson::doc;
+use mongodb::Collection;
+use mongodb::Database;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use tonic::{Request, Status};
+
+use crate::models::{AccessTokenRecord, AuthGrant, RegisteredClient};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    ProfileRead,
+    SquadWrite,
+    RewardClaim,
+}
+
+impl Scope {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Scope::ProfileRead => "profile:read",
+            Scope::SquadWrite => "squad:write",
+            Scope::RewardClaim => "reward:claim",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "profile:read" => Some(Scope::ProfileRead),
+            "squad:write" => Some(Scope::SquadWrite),
+            "reward:claim" => Some(Scope::RewardClaim),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Scopes granted to the bearer token on the current request, attached
+/// by `ScopeInterceptor`.
+#[derive(Debug, Clone)]
+pub struct Scopes(pub HashSet<Scope>);
+
+/// Checks that the interceptor granted `scope` for this request,
+/// returning `Status::permission_denied` otherwise, or
+/// `Status::unauthenticated` if no `Scopes` were attached at all.
+pub fn require_scope<T>(request: &Request<T>, scope: Scope) -> Result<(), Status> {
+    let granted = request
+        .extensions()
+        .get::<Scopes>()
+        .ok_or_else(|| Status::unauthenticated("missing bearer token"))?;
+
+    if granted.0.contains(&scope) {
+        Ok(())
+    } else {
+        Err(Status::permission_denied(format!(
+            "missing required scope: {scope}"
+        )))
+    }
+}
+
+#[derive(Clone)]
+pub struct AuthState {
+    grants: Collection<AuthGrant>,
+    tokens: Collection<AccessTokenRecord>,
+    registered_clients: Collection<RegisteredClient>,
+    cache: Arc<RwLock<HashMap<String, Scopes>>>,
+}
+
+impl AuthState {
+    pub fn new(db: &Database) -> Self {
+        Self {
+            grants: db.collection("auth_grants"),
+            tokens: db.collection("auth_tokens"),
+            registered_clients: db.collection("registered_clients"),
+            cache: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn cache(&self) -> Arc<RwLock<HashMap<String, Scopes>>> {
+        self.cache.clone()
+    }
+
+    /// Rehydrates the in-memory verification cache from
+    /// `auth_tokens`, so `ScopeInterceptor` keeps working across a
+    /// restart without re-issuing tokens.
+    pub async fn load_cache(&self) -> Result<(), mongodb::error::Error> {
+        use futures::stream::TryStreamExt;
+
+        let mut cursor = self.tokens.find(doc! {}, None).await?;
+        let mut cache = self.cache.write().unwrap();
+        while let Some(record) = cursor.try_next().await? {
+            let scopes = record
+                .scopes
+                .iter()
+                .filter_map(|s| Scope::parse(s))
+                .collect();
+            cache.insert(record.token_hash, Scopes(scopes));
+        }
+        Ok(())
+    }
+
+    /// Step one of the PKCE flow: checks `scopes` against the calling
+    /// client's entry in `registered_clients`, then records the
+    /// `code_challenge` against a freshly-minted authorization `code`.
+    pub async fn authorize(
+        &self,
+        client_id: String,
+        scopes: Vec<String>,
+        code_challenge: String,
+        code_challenge_method: String,
+    ) -> Result<String, Status> {
+        if code_challenge_method != "S256" {
+            return Err(Status::invalid_argument(
+                "only S256 code_challenge_method is supported",
+            ));
+        }
+
+        let client = self
+            .registered_clients
+            .find_one(doc! { "client_id": &client_id }, None)
+            .await
+            .map_err(|e| Status::internal(format!("failed to load client policy: {e}")))?
+            .ok_or_else(|| Status::unauthenticated("unknown client_id"))?;
+
+        for scope in &scopes {
+            if !client.allowed_scopes.iter().any(|allowed| allowed == scope) {
+                return Err(Status::permission_denied(format!(
+                    "client {client_id} is not permitted to request scope: {scope}"
+                )));
+            }
+        }
+
+        let code = random_urlsafe_token();
+        self.grants
+            .insert_one(
+                AuthGrant {
+                    id: None,
+                    code: code.clone(),
+                    client_id,
+                    scopes,
+                    code_challenge,
+                    code_challenge_method,
+                    consumed: false,
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Status::internal(format!("failed to persist authorization: {e}")))?;
+
+        Ok(code)
+    }
+
+    /// Step two of the PKCE flow: recomputes
+    /// `BASE64URL(SHA256(code_verifier))` and rejects the exchange
+    /// unless it matches the stored challenge, using a constant-time
+    /// comparison to avoid leaking the challenge through timing.
+    ///
+    /// Claims the grant with a single `find_one_and_update` rather than
+    /// a `find_one` followed by a separate `update_one`, so two
+    /// concurrent exchanges of the same code can't both observe
+    /// `consumed: false` and both succeed.
+    pub async fn exchange(
+        &self,
+        code: String,
+        code_verifier: String,
+    ) -> Result<(String, Vec<String>), Status> {
+        let grant = self
+            .grants
+            .find_one_and_update(
+                doc! { "code": &code, "consumed": false },
+                doc! { "$set": { "consumed": true } },
+                None,
+            )
+            .await
+            .map_err(|e| Status::internal(format!("failed to consume authorization: {e}")))?
+            .ok_or_else(|| Status::unauthenticated("unknown or already-used code"))?;
+
+        let expected = compute_pkce_challenge(&code_verifier);
+        if !constant_time_eq(expected.as_bytes(), grant.code_challenge.as_bytes()) {
+            return Err(Status::unauthenticated("code_verifier does not match"));
+        }
+
+        let token = random_urlsafe_token();
+        let token_hash = hash_token(&token);
+
+        self.tokens
+            .insert_one(
+                AccessTokenRecord {
+                    id: None,
+                    token_hash: token_hash.clone(),
+                    client_id: grant.client_id,
+                    scopes: grant.scopes.clone(),
+                },
+                None,
+            )
+            .await
+            .map_err(|e| Status::internal(format!("failed to persist access token: {e}")))?;
+
+        let scopes: HashSet<Scope> = grant.scopes.iter().filter_map(|s| Scope::parse(s)).collect();
+        self.cache.write().unwrap().insert(token_hash, Scopes(scopes));
+
+        Ok((token, grant.scopes))
+    }
+}
+
+/// Verifies the bearer token on every `GameBlocService` call and
+/// attaches its `Scopes` to the request's extensions for handlers to
+/// check with `require_scope`.
+#[derive(Clone)]
+pub struct ScopeInterceptor {
+    cache: Arc<RwLock<HashMap<String, Scopes>>>,
+}
+
+impl ScopeInterceptor {
+    pub fn new(cache: Arc<RwLock<HashMap<String, Scopes>>>) -> Self {
+        Self { cache }
+    }
+}
+
+impl tonic::service::Interceptor for ScopeInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let token = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or_else(|| Status::unauthenticated("missing bearer token"))?;
+
+        let token_hash = hash_token(token);
+        let scopes = self
+            .cache
+            .read()
+            .unwrap()
+            .get(&token_hash)
+            .cloned()
+            .ok_or_else(|| Status::unauthenticated("invalid or expired bearer token"))?;
+
+        request.extensions_mut().insert(scopes);
+        Ok(request)
+    }
+}
+
+fn random_urlsafe_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn hash_token(token: &str) -> String {
+    let digest = Sha256::digest(token.as_bytes());
+    URL_SAFE_NO_PAD.encode(digest)
+}
+
+fn compute_pkce_challenge(code_verifier: &str) -> String {
+    let digest = Sha256::digest(code_verifier.as_bytes());
+    URL_SAFE_NO_PAD.encode(digest)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("verifier-one")]
+    #[case("")]
+    #[case("a much longer verifier string, just to be sure")]
+    fn compute_pkce_challenge_is_deterministic_and_input_sensitive(#[case] verifier: &str) {
+        assert_eq!(
+            compute_pkce_challenge(verifier),
+            compute_pkce_challenge(verifier)
+        );
+        assert_ne!(
+            compute_pkce_challenge(verifier),
+            compute_pkce_challenge(&format!("{verifier}x"))
+        );
+    }
+
+    #[rstest]
+    #[case(b"abc" as &[u8], b"abc" as &[u8], true)]
+    #[case(b"abc", b"abd", false)]
+    #[case(b"abc", b"ab", false)]
+    #[case(b"", b"", true)]
+    fn constant_time_eq_matches_slice_equality(
+        #[case] a: &[u8],
+        #[case] b: &[u8],
+        #[case] expected: bool,
+    ) {
+        assert_eq!(constant_time_eq(a, b), expected);
+    }
+
+    /// Builds an `AuthState` against a lazily-connected local MongoDB,
+    /// the same pattern `GameBlocServiceState::test_state` uses, and
+    /// registers `client_id` for `allowed_scopes` so `authorize` doesn't
+    /// reject it outright.
+    async fn test_auth_state(client_id: &str, allowed_scopes: &[&str]) -> AuthState {
+        let db = mongodb::Client::with_uri_str("mongodb://localhost:27017")
+            .await
+            .expect("parsing a static connection string never fails")
+            .database("test");
+        let auth = AuthState::new(&db);
+
+        auth.registered_clients
+            .insert_one(
+                RegisteredClient {
+                    id: None,
+                    client_id: client_id.to_string(),
+                    allowed_scopes: allowed_scopes.iter().map(|s| s.to_string()).collect(),
+                },
+                None,
+            )
+            .await
+            .expect("seeding a registered client should succeed");
+
+        auth
+    }
+
+    #[tokio::test]
+    async fn authorize_rejects_scopes_outside_the_clients_allow_list() {
+        let auth = test_auth_state("test-client", &["profile:read"]).await;
+
+        let result = auth
+            .authorize(
+                "test-client".into(),
+                vec!["profile:read".into(), "squad:write".into()],
+                compute_pkce_challenge("verifier"),
+                "S256".into(),
+            )
+            .await;
+
+        assert_eq!(result.unwrap_err().code(), tonic::Code::PermissionDenied);
+    }
+
+    #[tokio::test]
+    async fn authorize_rejects_unregistered_clients() {
+        let auth = test_auth_state("known-client", &["profile:read"]).await;
+
+        let result = auth
+            .authorize(
+                "unknown-client".into(),
+                vec!["profile:read".into()],
+                compute_pkce_challenge("verifier"),
+                "S256".into(),
+            )
+            .await;
+
+        assert_eq!(result.unwrap_err().code(), tonic::Code::Unauthenticated);
+    }
+
+    /// Drives two concurrent `exchange` calls for the same authorization
+    /// code and asserts only one succeeds, proving the atomic
+    /// `find_one_and_update` claim actually closes the TOCTOU race a
+    /// `find_one` followed by a separate `update_one` would leave open.
+    #[tokio::test]
+    async fn exchange_only_consumes_a_code_once() {
+        let auth = test_auth_state("test-client", &["profile:read"]).await;
+        let code_verifier = "s3cr3t-verifier";
+
+        let code = auth
+            .authorize(
+                "test-client".into(),
+                vec!["profile:read".into()],
+                compute_pkce_challenge(code_verifier),
+                "S256".into(),
+            )
+            .await
+            .expect("authorize should succeed for an allowed scope");
+
+        let (first, second) = tokio::join!(
+            auth.exchange(code.clone(), code_verifier.into()),
+            auth.exchange(code, code_verifier.into())
+        );
+
+        let successes = [&first, &second].into_iter().filter(|r| r.is_ok()).count();
+        assert_eq!(successes, 1, "exactly one concurrent exchange should succeed");
+    }
+}
+
+#[tonic::async_trait]
+impl centrium_grpc_server::auth_service_server::AuthService for AuthState {
+    async fn authorize(
+        &self,
+        request: Request<centrium_grpc_server::AuthorizeRequest>,
+    ) -> Result<tonic::Response<centrium_grpc_server::AuthorizeResponse>, Status> {
+        let req = request.into_inner();
+        let code = AuthState::authorize(
+            self,
+            req.client_id,
+            req.scopes,
+            req.code_challenge,
+            req.code_challenge_method,
+        )
+        .await?;
+
+        Ok(tonic::Response::new(
+            centrium_grpc_server::AuthorizeResponse { code },
+        ))
+    }
+
+    async fn exchange_token(
+        &self,
+        request: Request<centrium_grpc_server::ExchangeTokenRequest>,
+    ) -> Result<tonic::Response<centrium_grpc_server::ExchangeTokenResponse>, Status> {
+        let req = request.into_inner();
+        let (access_token, scopes) = AuthState::exchange(self, req.code, req.code_verifier).await?;
+
+        Ok(tonic::Response::new(
+            centrium_grpc_server::ExchangeTokenResponse {
+                access_token,
+                scopes,
+            },
+        ))
+    }
+}
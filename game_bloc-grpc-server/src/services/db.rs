@@ -1,127 +1,163 @@
+use std::fmt;
+use std::sync::Arc;
 
-// use serde::{Serialize, Deserialize};
-//
-// #[derive(Debug, Serialize, Deserialize)]
-// struct User {
-//     _id: ObjectId,
-//     name: String,
-//     email: String,
-// }
-//
-// #[derive(Debug, Serialize, Deserialize)]
-// struct Product {
-//     _id: ObjectId,
-//     name: String,
-//     description: String,
-//     price: f64,
-// }
-//
-// #[derive(Debug)]
-// struct StoreServiceImpl {
-//     users_collection: Collection<User>,
-//     products_collection: Collection<Product>,
-// }
-//
-// #[tonic::async_trait]
-// impl StoreService for StoreServiceImpl {
-//     // async fn create_user(
-//     //     &self,
-//     //     request: Request<CreateUserRequest>,
-//     // ) -> Result<Response<UserResponse>, Status> {
-//     //     let req = request.into_inner();
-//     //     let user = User {
-//     //         _id: ObjectId::new(),
-//     //         name: req.name,
-//     //         email: req.email,
-//     //     };
-//     //
-//     //     self.users_collection
-//     //         .insert_one(user, None)
-//     //         .await
-//     //         .map_err(|e| Status::internal(format!("MongoDB error: {}", e))?;
-//     //
-//     //     Ok(Response::new(UserResponse {
-//     //         id: user._id.to_hex(),
-//     //         name: req.name,
-//     //         email: req.email,
-//     //     }))
-//     // }
-//     //
-//     // async fn get_user(
-//     //     &self,
-//     //     request: Request<GetUserRequest>,
-//     // ) -> Result<Response<UserResponse>, Status> {
-//     //     let id = request.into_inner().id;
-//     //     let oid = ObjectId::parse_str(&id).map_err(|_| Status::invalid_argument("Invalid ID"))?;
-//     //
-//     //     let user = self.users_collection
-//     //         .find_one(doc! { "_id": oid }, None)
-//     //         .await
-//     //         .map_err(|e| Status::internal(format!("MongoDB error: {}", e)))?
-//     //         .ok_or_else(|| Status::not_found("User not found"))?;
-//     //
-//     //     Ok(Response::new(UserResponse {
-//     //         id: user._id.to_hex(),
-//     //         name: user.name,
-//     //         email: user.email,
-//     //     }))
-//     // }
-//     //
-//     // async fn create_product(
-//     //     &self,
-//     //     request: Request<CreateProductRequest>,
-//     // ) -> Result<Response<ProductResponse>, Status> {
-//     //     let req = request.into_inner();
-//     //     let product = Product {
-//     //         _id: ObjectId::new(),
-//     //         name: req.name,
-//     //         description: req.description,
-//     //         price: req.price as f64,
-//     //     };
-//     //
-//     //     self.products_collection
-//     //         .insert_one(product, None)
-//     //         .await
-//     //         .map_err(|e| Status::internal(format!("MongoDB error: {}", e)))?;
-//     //
-//     //     Ok(Response::new(ProductResponse {
-//     //         id: product._id.to_hex(),
-//     //         name: req.name,
-//     //         description: req.description,
-//     //         price: req.price,
-//     //     }))
-//     // }
-//     //
-//     // async fn get_product(
-//     //     &self,
-//     //     request: Request<GetProductRequest>,
-//     // ) -> Result<Response<ProductResponse>, Status> {
-//     //     let id = request.into_inner().id;
-//     //     let oid = ObjectId::parse_str(&id).map_err(|_| Status::invalid_argument("Invalid ID"))?;
-//     //
-//     //     let product = self.products_collection
-//     //         .find_one(doc! { "_id": oid }, None)
-//     //         .await
-//     //         .map_err(|e| Status::internal(format!("MongoDB error: {}", e)))?
-//     //         .ok_or_else(|| Status::not_found("Product not found"))?;
-//     //
-//     //     Ok(Response::new(ProductResponse {
-//     //         id: product._id.to_hex(),
-//     //         name: product.name,
-//     //         description: product.description,
-//     //         price: product.price,
-//     //     }))
-//     // }
-// }
-//
-#[derive(Debug, Default)]
-struct GameBlocServiceState {
-    profile_store: Collection<Booking>,
-    tournament_store: Collection<Dog>,
-    squad_store: Collection<Owner>,
-    games_store: Collection<Owner>,
-    notification_store: Collection<Owner>,
-    message_store: Collection<Owner>,
-    daily_reward_store: Collection<Owner>,
-    referral_map: Collection<Owner>,
+use mongodb::bson::doc;
+use mongodb::Collection;
+use mongodb::Database;
+
+use super::auth::AuthState;
+use super::i18n::I18n;
+use super::repository::{self, Repository};
+use super::search::SearchIndex;
+use crate::error::RepoError;
+use crate::models::{NotificationCursor, Owner, ProfileDocument, TournamentDocument};
+
+/// State shared by every RPC handler. CRUD-only stores sit behind
+/// `Repository<T>` so tests can swap in `InMemoryRepository`; stores
+/// that need MongoDB-specific features (`notification_store`'s change
+/// stream, `notification_cursor_store`'s upsert by `owner_id`) keep
+/// their concrete `Collection<T>`.
+#[derive(Clone)]
+pub struct GameBlocServiceState {
+    pub(crate) db: Database,
+    pub(crate) profile_store: Arc<dyn Repository<ProfileDocument>>,
+    pub(crate) tournament_store: Arc<dyn Repository<TournamentDocument>>,
+    pub(crate) squad_store: Arc<dyn Repository<Owner>>,
+    pub(crate) games_store: Arc<dyn Repository<Owner>>,
+    pub(crate) notification_store: Collection<Owner>,
+    pub(crate) message_store: Collection<Owner>,
+    pub(crate) daily_reward_store: Arc<dyn Repository<Owner>>,
+    pub(crate) referral_map: Arc<dyn Repository<Owner>>,
+    pub(crate) notification_cursor_store: Collection<NotificationCursor>,
+    pub(crate) search_index: Arc<SearchIndex>,
+    pub(crate) auth: AuthState,
+    pub(crate) i18n: Arc<I18n>,
+}
+
+impl fmt::Debug for GameBlocServiceState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GameBlocServiceState").finish_non_exhaustive()
+    }
+}
+
+impl GameBlocServiceState {
+    pub fn new(db: Database) -> Self {
+        let search_index = Arc::new(SearchIndex::new());
+
+        Self {
+            profile_store: repository::indexed(
+                repository::mongo(db.collection("profiles")),
+                search_index.clone(),
+                "profiles",
+                profile_search_fields,
+            ),
+            tournament_store: repository::mongo(db.collection("tournaments")),
+            squad_store: repository::indexed(
+                repository::mongo(db.collection("squads")),
+                search_index.clone(),
+                "squads",
+                owner_payload_search_fields,
+            ),
+            games_store: repository::indexed(
+                repository::mongo(db.collection("games")),
+                search_index.clone(),
+                "games",
+                owner_payload_search_fields,
+            ),
+            notification_store: db.collection("notifications"),
+            message_store: db.collection("messages"),
+            daily_reward_store: repository::mongo(db.collection("daily_rewards")),
+            referral_map: repository::mongo(db.collection("referrals")),
+            notification_cursor_store: db.collection("notification_cursors"),
+            search_index,
+            auth: AuthState::new(&db),
+            i18n: Arc::new(I18n::load()),
+            db,
+        }
+    }
+
+    /// Pings the backing MongoDB deployment. Used by the health service
+    /// to flip `SERVING` to `NOT_SERVING` when the database is
+    /// unreachable.
+    pub async fn ping(&self) -> bool {
+        self.db.run_command(doc! { "ping": 1 }, None).await.is_ok()
+    }
+
+    /// Builds the search index from scratch by scanning `profile_store`,
+    /// `squad_store` and `games_store`. Called once at startup to cover
+    /// documents that existed before this process started; from then on
+    /// `IndexedRepository` keeps the index current as writes land on
+    /// those same stores.
+    pub async fn reindex_search(&self) -> Result<(), RepoError> {
+        for profile in self.profile_store.find(doc! {}).await? {
+            if let Some(id) = profile.id {
+                self.search_index.upsert_document(
+                    "profiles",
+                    &id.to_hex(),
+                    &[
+                        ("owner_id", &profile.owner_id),
+                        ("display_name", &profile.display_name),
+                    ],
+                );
+            }
+        }
+
+        for (name, store) in [
+            ("squads", &self.squad_store),
+            ("games", &self.games_store),
+        ] {
+            for item in store.find(doc! {}).await? {
+                if let Some(id) = item.id {
+                    let payload = item.payload.to_string();
+                    self.search_index
+                        .upsert_document(name, &id.to_hex(), &[("payload", &payload)]);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds state for unit tests: `profile_store` is whatever the
+    /// test provides (typically `repository::in_memory()`), and every
+    /// other store is an in-memory or lazily-connected stand-in, since
+    /// the driver doesn't establish a real connection until a command
+    /// actually runs.
+    #[cfg(test)]
+    pub(crate) async fn test_state(profile_store: Arc<dyn Repository<ProfileDocument>>) -> Self {
+        let db = mongodb::Client::with_uri_str("mongodb://localhost:27017")
+            .await
+            .expect("parsing a static connection string never fails")
+            .database("test");
+
+        Self {
+            profile_store,
+            tournament_store: repository::in_memory(),
+            squad_store: repository::in_memory(),
+            games_store: repository::in_memory(),
+            notification_store: db.collection("notifications"),
+            message_store: db.collection("messages"),
+            daily_reward_store: repository::in_memory(),
+            referral_map: repository::in_memory(),
+            notification_cursor_store: db.collection("notification_cursors"),
+            search_index: Arc::new(SearchIndex::new()),
+            auth: AuthState::new(&db),
+            i18n: Arc::new(I18n::load()),
+            db,
+        }
+    }
+}
+
+/// Field extractor for `IndexedRepository<ProfileDocument>`.
+fn profile_search_fields(profile: &ProfileDocument) -> Vec<(&'static str, String)> {
+    vec![
+        ("owner_id", profile.owner_id.clone()),
+        ("display_name", profile.display_name.clone()),
+    ]
+}
+
+/// Field extractor for `IndexedRepository<Owner>`, shared by
+/// `squad_store` and `games_store`.
+fn owner_payload_search_fields(owner: &Owner) -> Vec<(&'static str, String)> {
+    vec![("payload", owner.payload.to_string())]
 }
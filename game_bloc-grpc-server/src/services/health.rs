@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use tonic_health::server::HealthReporter;
+use tonic_health::ServingStatus;
+
+use super::db::GameBlocServiceState;
+
+/// Service name reported through `grpc.health.v1.Health`, matching the
+/// fully-qualified name tonic_reflection exposes for `GameBlocService`.
+pub const GAME_BLOC_SERVICE_NAME: &str = "centrium.GameBlocService";
+
+/// Fully-qualified name for the public `AuthService`. Its `Authorize` and
+/// `ExchangeToken` handlers read and write `state`'s database (registered
+/// clients, grants, tokens), so it tracks `GAME_BLOC_SERVICE_NAME`'s
+/// status rather than being reported separately.
+pub const AUTH_SERVICE_NAME: &str = "centrium.AuthService";
+
+const PING_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Keeps the health service's `SERVING`/`NOT_SERVING` status for
+/// `GAME_BLOC_SERVICE_NAME`, `AUTH_SERVICE_NAME`, and the overall-server
+/// entry (`""`) in sync with MongoDB reachability, polling on
+/// `PING_INTERVAL`. Runs until the process exits.
+pub async fn monitor(mut reporter: HealthReporter, state: GameBlocServiceState) {
+    let mut ticker = tokio::time::interval(PING_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let status = if state.ping().await {
+            ServingStatus::Serving
+        } else {
+            ServingStatus::NotServing
+        };
+        for name in ["", GAME_BLOC_SERVICE_NAME, AUTH_SERVICE_NAME] {
+            reporter.set_service_status(name, status).await;
+        }
+    }
+}
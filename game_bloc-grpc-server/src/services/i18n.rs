@@ -0,0 +1,156 @@
+//! Fluent-backed localization for gRPC error messages and notification
+//! text. Bundles are loaded once at startup from `locales/<tag>/main.ftl`
+//! and kept in application state; the locale for a given call is
+//! negotiated from its `accept-language` metadata.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+use fluent_langneg::{negotiate_languages, NegotiationStrategy};
+use tonic::Request;
+use unic_langid::LanguageIdentifier;
+
+const LOCALES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/locales");
+const DEFAULT_LOCALE: &str = "en-US";
+
+pub struct I18n {
+    bundles: HashMap<LanguageIdentifier, FluentBundle<FluentResource>>,
+    default_locale: LanguageIdentifier,
+}
+
+impl I18n {
+    /// Walks `locales/<tag>/main.ftl` under the crate root (resolved via
+    /// `CARGO_MANIFEST_DIR` so this doesn't depend on the process's
+    /// working directory) and builds one `FluentBundle` per discovered
+    /// locale tag.
+    ///
+    /// Panics if no bundle loads at all: every `message()` call would
+    /// otherwise silently degrade to returning bare Fluent keys, which
+    /// is worse than failing loudly at startup.
+    pub fn load() -> Self {
+        let mut bundles = HashMap::new();
+        let default_locale: LanguageIdentifier =
+            DEFAULT_LOCALE.parse().expect("DEFAULT_LOCALE is a valid language tag");
+
+        let entries = fs::read_dir(LOCALES_DIR)
+            .unwrap_or_else(|e| panic!("failed to read locales dir {LOCALES_DIR}: {e}"));
+        for entry in entries.flatten() {
+            let Some(tag) = entry.file_name().to_str().map(str::to_owned) else {
+                continue;
+            };
+            let Ok(langid) = tag.parse::<LanguageIdentifier>() else {
+                continue;
+            };
+            if let Some(bundle) = load_bundle(&langid, &entry.path()) {
+                bundles.insert(langid, bundle);
+            }
+        }
+
+        if bundles.is_empty() {
+            panic!("no Fluent bundles loaded from {LOCALES_DIR}");
+        }
+
+        Self {
+            bundles,
+            default_locale,
+        }
+    }
+
+    /// Negotiates the best available locale for `accept-language`
+    /// (e.g. `"fr-FR,fr;q=0.9,en;q=0.8"`), falling back to
+    /// `DEFAULT_LOCALE` when nothing matches.
+    pub fn negotiate(&self, accept_language: Option<&str>) -> LanguageIdentifier {
+        let requested: Vec<LanguageIdentifier> = accept_language
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|part| part.split(';').next())
+            .filter_map(|tag| tag.trim().parse().ok())
+            .collect();
+
+        let available: Vec<&LanguageIdentifier> = self.bundles.keys().collect();
+
+        negotiate_languages(
+            &requested,
+            &available,
+            Some(&self.default_locale),
+            NegotiationStrategy::Filtering,
+        )
+        .first()
+        .map(|l| (*l).clone())
+        .unwrap_or_else(|| self.default_locale.clone())
+    }
+
+    /// Resolves `key` in `locale` (falling back to `DEFAULT_LOCALE`,
+    /// then to the bare key) and formats it with `args`.
+    pub fn message(&self, locale: &LanguageIdentifier, key: &str, args: Option<&FluentArgs>) -> String {
+        for candidate in [locale, &self.default_locale] {
+            let Some(bundle) = self.bundles.get(candidate) else {
+                continue;
+            };
+            let Some(message) = bundle.get_message(key) else {
+                continue;
+            };
+            let Some(pattern) = message.value() else {
+                continue;
+            };
+            let mut errors = Vec::new();
+            return bundle
+                .format_pattern(pattern, args, &mut errors)
+                .into_owned();
+        }
+        key.to_string()
+    }
+}
+
+fn load_bundle(langid: &LanguageIdentifier, dir: &Path) -> Option<FluentBundle<FluentResource>> {
+    let source = fs::read_to_string(dir.join("main.ftl")).ok()?;
+    let resource = FluentResource::try_new(source)
+        .map_err(|(_, errors)| tracing::warn!(?errors, ?langid, "failed to parse .ftl bundle"))
+        .ok()?;
+
+    let mut bundle = FluentBundle::new(vec![langid.clone()]);
+    bundle
+        .add_resource(resource)
+        .unwrap_or_else(|errors| tracing::warn!(?errors, ?langid, "duplicate Fluent messages"));
+    Some(bundle)
+}
+
+/// Reads the `accept-language` gRPC metadata value, if present.
+pub fn accept_language<T>(request: &Request<T>) -> Option<&str> {
+    request
+        .metadata()
+        .get("accept-language")
+        .and_then(|v| v.to_str().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_locale() -> LanguageIdentifier {
+        DEFAULT_LOCALE.parse().unwrap()
+    }
+
+    #[test]
+    fn negotiate_picks_the_best_matching_available_locale() {
+        let i18n = I18n::load();
+        let locale = i18n.negotiate(Some("fr-FR,fr;q=0.9,en;q=0.8"));
+        assert_eq!(locale, "fr".parse::<LanguageIdentifier>().unwrap());
+    }
+
+    #[test]
+    fn negotiate_falls_back_to_the_default_locale_when_nothing_matches() {
+        let i18n = I18n::load();
+        let locale = i18n.negotiate(Some("de-DE,de;q=0.9"));
+        assert_eq!(locale, default_locale());
+    }
+
+    #[test]
+    fn negotiate_falls_back_to_the_default_locale_when_the_header_is_absent() {
+        let i18n = I18n::load();
+        let locale = i18n.negotiate(None);
+        assert_eq!(locale, default_locale());
+    }
+}
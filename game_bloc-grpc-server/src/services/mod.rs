@@ -0,0 +1,8 @@
+pub mod auth;
+pub mod db;
+pub mod health;
+pub mod i18n;
+pub mod notifications;
+pub mod repository;
+pub mod rpc;
+pub mod search;
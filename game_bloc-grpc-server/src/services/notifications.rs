@@ -0,0 +1,191 @@
+use bson::oid::ObjectId;
+use bson::{doc, Document};
+use fluent_bundle::FluentArgs;
+use futures::stream::StreamExt;
+use mongodb::change_stream::event::ResumeToken;
+use mongodb::options::{ChangeStreamOptions, FullDocumentType};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::Status;
+use unic_langid::LanguageIdentifier;
+
+use centrium_grpc_server::NotificationEvent;
+
+use super::db::GameBlocServiceState;
+use crate::models::{NotificationCursor, Owner};
+
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Delay before re-opening a dropped change stream, so a flapping
+/// connection to MongoDB doesn't spin the reconnect loop at full speed.
+const RECONNECT_BACKOFF: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Opens a server-streaming feed of notifications for `owner_id`, backed
+/// by a MongoDB change stream on `notification_store` filtered to that
+/// owner. `resume_token`, if supplied, takes precedence over whatever
+/// was last persisted for the owner so a reconnecting client can pick
+/// up exactly where it left off.
+pub async fn subscribe(
+    state: GameBlocServiceState,
+    owner_id: String,
+    resume_token: Option<Document>,
+    locale: LanguageIdentifier,
+) -> Result<ReceiverStream<Result<NotificationEvent, Status>>, Status> {
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+    let start_after = match resume_token {
+        Some(token) => Some(token),
+        None => load_cursor(&state, &owner_id).await?,
+    };
+
+    tokio::spawn(run(state, owner_id, start_after, locale, tx));
+
+    Ok(ReceiverStream::new(rx))
+}
+
+async fn load_cursor(
+    state: &GameBlocServiceState,
+    owner_id: &str,
+) -> Result<Option<Document>, Status> {
+    state
+        .notification_cursor_store
+        .find_one(doc! { "owner_id": owner_id }, None)
+        .await
+        .map_err(|e| Status::internal(format!("failed to load notification cursor: {e}")))
+        .map(|cursor| cursor.map(|c| c.resume_token))
+}
+
+async fn persist_cursor(state: &GameBlocServiceState, owner_id: &str, token: &Document) {
+    let result = state
+        .notification_cursor_store
+        .update_one(
+            doc! { "owner_id": owner_id },
+            doc! { "$set": { "resume_token": token, "owner_id": owner_id } },
+            mongodb::options::UpdateOptions::builder()
+                .upsert(true)
+                .build(),
+        )
+        .await;
+
+    if let Err(e) = result {
+        tracing::warn!(owner_id, error = %e, "failed to persist notification resume token");
+    }
+}
+
+/// Drives the change stream for `owner_id`, forwarding events into
+/// `tx` and re-opening from the last persisted resume token whenever
+/// the underlying cursor drops.
+async fn run(
+    state: GameBlocServiceState,
+    owner_id: String,
+    mut start_after: Option<Document>,
+    locale: LanguageIdentifier,
+    tx: mpsc::Sender<Result<NotificationEvent, Status>>,
+) {
+    loop {
+        let pipeline = vec![doc! { "$match": { "fullDocument.owner_id": &owner_id } }];
+        let mut options = ChangeStreamOptions::builder()
+            .full_document(Some(FullDocumentType::UpdateLookup))
+            .build();
+        options.start_after = start_after.clone();
+
+        let mut cursor = match state.notification_store.watch(pipeline, options).await {
+            Ok(cursor) => cursor,
+            Err(e) => {
+                let _ = tx
+                    .send(Err(Status::unavailable(format!(
+                        "change stream unavailable: {e}"
+                    ))))
+                    .await;
+                return;
+            }
+        };
+
+        while let Some(event) = cursor.next().await {
+            let event = match event {
+                Ok(event) => event,
+                Err(_) => break, // cursor dropped; reopen below from the last persisted token
+            };
+
+            let Some(token) = cursor.resume_token() else {
+                continue;
+            };
+            let token_doc = resume_token_to_doc(&token);
+            persist_cursor(&state, &owner_id, &token_doc).await;
+            start_after = Some(token_doc.clone());
+
+            let Some(doc) = event.full_document else {
+                continue;
+            };
+            let notification: Owner = match bson::from_document(doc) {
+                Ok(n) => n,
+                Err(_) => continue,
+            };
+
+            let mut payload = notification.payload.clone();
+            if let Some(text) = localize_squad_invite(&state, &locale, &notification.payload) {
+                payload.insert("localized_text", text.clone());
+                if let Some(id) = notification.id {
+                    persist_localized_text(&state, id, &text).await;
+                }
+            }
+
+            let proto_event = NotificationEvent {
+                id: notification.id.map(|id| id.to_hex()).unwrap_or_default(),
+                owner_id: owner_id.clone(),
+                payload: serde_json::to_string(&payload).unwrap_or_default(),
+                resume_token: serde_json::to_string(&token_doc).unwrap_or_default(),
+            };
+
+            if tx.send(Ok(proto_event)).await.is_err() {
+                return; // client disconnected
+            }
+        }
+
+        tokio::time::sleep(RECONNECT_BACKOFF).await;
+    }
+}
+
+fn resume_token_to_doc(token: &ResumeToken) -> Document {
+    bson::to_document(token).unwrap_or_default()
+}
+
+/// Localizes a `kind: "squad_invite"` notification payload via
+/// `notification-squad-invite`, returning `None` for any other kind of
+/// notification.
+fn localize_squad_invite(
+    state: &GameBlocServiceState,
+    locale: &LanguageIdentifier,
+    payload: &Document,
+) -> Option<String> {
+    if payload.get_str("kind").ok()? != "squad_invite" {
+        return None;
+    }
+    let inviter = payload.get_str("inviter").ok()?;
+    let squad = payload.get_str("squad").ok()?;
+
+    let mut args = FluentArgs::new();
+    args.set("inviter", inviter);
+    args.set("squad", squad);
+
+    Some(
+        state
+            .i18n
+            .message(locale, "notification-squad-invite", Some(&args)),
+    )
+}
+
+async fn persist_localized_text(state: &GameBlocServiceState, id: ObjectId, text: &str) {
+    let result = state
+        .notification_store
+        .update_one(
+            doc! { "_id": id },
+            doc! { "$set": { "payload.localized_text": text } },
+            None,
+        )
+        .await;
+
+    if let Err(e) = result {
+        tracing::warn!(id = %id, error = %e, "failed to persist localized notification text");
+    }
+}
@@ -0,0 +1,265 @@
+//! Storage abstraction for the CRUD-style stores on `GameBlocServiceState`.
+//!
+//! `GameBlocServiceState` depends on `Arc<dyn Repository<T>>` rather
+//! than a concrete `mongodb::Collection<T>`, so RPC handlers can be
+//! exercised with `InMemoryRepository` in tests without a live MongoDB.
+//! Stores that need MongoDB-specific features (change streams on
+//! `notification_store`, upserts keyed by `owner_id` on
+//! `notification_cursor_store`) keep their concrete `Collection<T>`
+//! instead — apart from the filter-based `find`, the trait addresses
+//! documents by id only.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use mongodb::bson::{doc, oid::ObjectId, Document};
+use mongodb::Collection;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+use super::search::SearchIndex;
+use crate::error::RepoError;
+use crate::models::Identifiable;
+
+#[tonic::async_trait]
+pub trait Repository<T>: Send + Sync {
+    async fn insert_one(&self, item: T) -> Result<ObjectId, RepoError>;
+    async fn find_one(&self, id: ObjectId) -> Result<Option<T>, RepoError>;
+    async fn find(&self, filter: Document) -> Result<Vec<T>, RepoError>;
+    async fn update_one(&self, id: ObjectId, update: Document) -> Result<(), RepoError>;
+    async fn delete_one(&self, id: ObjectId) -> Result<(), RepoError>;
+}
+
+/// Production `Repository` backed by a MongoDB collection.
+pub struct MongoRepository<T> {
+    collection: Collection<T>,
+}
+
+impl<T> MongoRepository<T> {
+    pub fn new(collection: Collection<T>) -> Self {
+        Self { collection }
+    }
+}
+
+#[tonic::async_trait]
+impl<T> Repository<T> for MongoRepository<T>
+where
+    T: Serialize + DeserializeOwned + Unpin + Send + Sync + 'static,
+{
+    async fn insert_one(&self, item: T) -> Result<ObjectId, RepoError> {
+        let result = self.collection.insert_one(item, None).await?;
+        result
+            .inserted_id
+            .as_object_id()
+            .ok_or_else(|| RepoError::Backend("insert did not return an ObjectId".into()))
+    }
+
+    async fn find_one(&self, id: ObjectId) -> Result<Option<T>, RepoError> {
+        Ok(self.collection.find_one(doc! { "_id": id }, None).await?)
+    }
+
+    async fn find(&self, filter: Document) -> Result<Vec<T>, RepoError> {
+        use futures::stream::TryStreamExt;
+        let cursor = self.collection.find(filter, None).await?;
+        Ok(cursor.try_collect().await?)
+    }
+
+    async fn update_one(&self, id: ObjectId, update: Document) -> Result<(), RepoError> {
+        self.collection
+            .update_one(doc! { "_id": id }, update, None)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_one(&self, id: ObjectId) -> Result<(), RepoError> {
+        self.collection.delete_one(doc! { "_id": id }, None).await?;
+        Ok(())
+    }
+}
+
+/// Test double backed by a `HashMap` guarded by an `RwLock`, so unit
+/// tests can drive `GameBlocServiceState` without Docker. Filters and
+/// updates only support flat equality/`$set` documents, which is all
+/// the current RPC handlers issue.
+#[derive(Default)]
+pub struct InMemoryRepository<T> {
+    items: RwLock<HashMap<ObjectId, T>>,
+}
+
+impl<T> InMemoryRepository<T> {
+    pub fn new() -> Self {
+        Self {
+            items: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<T> Repository<T> for InMemoryRepository<T>
+where
+    T: Identifiable + Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    async fn insert_one(&self, mut item: T) -> Result<ObjectId, RepoError> {
+        let id = item.id().unwrap_or_else(ObjectId::new);
+        item.set_id(id);
+        self.items.write().await.insert(id, item);
+        Ok(id)
+    }
+
+    async fn find_one(&self, id: ObjectId) -> Result<Option<T>, RepoError> {
+        Ok(self.items.read().await.get(&id).cloned())
+    }
+
+    async fn find(&self, filter: Document) -> Result<Vec<T>, RepoError> {
+        let items = self.items.read().await;
+        Ok(items
+            .values()
+            .filter(|item| matches_filter(item, &filter))
+            .cloned()
+            .collect())
+    }
+
+    async fn update_one(&self, id: ObjectId, update: Document) -> Result<(), RepoError> {
+        let mut items = self.items.write().await;
+        let Some(item) = items.get(&id) else {
+            return Err(RepoError::NotFound);
+        };
+
+        let mut as_doc =
+            bson::to_document(item).map_err(|e| RepoError::Backend(e.to_string()))?;
+        if let Some(set) = update.get_document("$set").ok() {
+            as_doc.extend(set.clone());
+        } else {
+            as_doc.extend(update);
+        }
+
+        let updated: T =
+            bson::from_document(as_doc).map_err(|e| RepoError::Backend(e.to_string()))?;
+        items.insert(id, updated);
+        Ok(())
+    }
+
+    async fn delete_one(&self, id: ObjectId) -> Result<(), RepoError> {
+        self.items.write().await.remove(&id);
+        Ok(())
+    }
+}
+
+/// Matches `item` against a flat equality `filter`, e.g. `{"owner_id": "abc"}`.
+fn matches_filter<T: Serialize>(item: &T, filter: &Document) -> bool {
+    if filter.is_empty() {
+        return true;
+    }
+    let Ok(doc) = bson::to_document(item) else {
+        return false;
+    };
+    filter
+        .iter()
+        .all(|(key, value)| doc.get(key).map(|v| v == value).unwrap_or(false))
+}
+
+/// Wraps another `Repository<T>` so every write also keeps `search_index`
+/// current, instead of only a one-shot startup scan touching it. `fields`
+/// extracts the `(name, text)` pairs to index from a document; `collection`
+/// is passed explicitly rather than derived from `T` because `Owner`
+/// backs more than one store (`squad_store`, `games_store`) under
+/// different collection names.
+pub struct IndexedRepository<T> {
+    inner: Arc<dyn Repository<T>>,
+    search_index: Arc<SearchIndex>,
+    collection: &'static str,
+    fields: fn(&T) -> Vec<(&'static str, String)>,
+}
+
+impl<T> IndexedRepository<T> {
+    pub fn new(
+        inner: Arc<dyn Repository<T>>,
+        search_index: Arc<SearchIndex>,
+        collection: &'static str,
+        fields: fn(&T) -> Vec<(&'static str, String)>,
+    ) -> Self {
+        Self {
+            inner,
+            search_index,
+            collection,
+            fields,
+        }
+    }
+
+    fn reindex(&self, id: &ObjectId, item: &T) {
+        let field_values = (self.fields)(item);
+        let field_refs: Vec<(&str, &str)> = field_values
+            .iter()
+            .map(|(field, value)| (*field, value.as_str()))
+            .collect();
+        self.search_index
+            .upsert_document(self.collection, &id.to_hex(), &field_refs);
+    }
+}
+
+#[tonic::async_trait]
+impl<T> Repository<T> for IndexedRepository<T>
+where
+    T: Send + Sync + 'static,
+{
+    async fn insert_one(&self, item: T) -> Result<ObjectId, RepoError> {
+        let field_values = (self.fields)(&item);
+        let id = self.inner.insert_one(item).await?;
+        let field_refs: Vec<(&str, &str)> = field_values
+            .iter()
+            .map(|(field, value)| (*field, value.as_str()))
+            .collect();
+        self.search_index
+            .upsert_document(self.collection, &id.to_hex(), &field_refs);
+        Ok(id)
+    }
+
+    async fn find_one(&self, id: ObjectId) -> Result<Option<T>, RepoError> {
+        self.inner.find_one(id).await
+    }
+
+    async fn find(&self, filter: Document) -> Result<Vec<T>, RepoError> {
+        self.inner.find(filter).await
+    }
+
+    async fn update_one(&self, id: ObjectId, update: Document) -> Result<(), RepoError> {
+        self.inner.update_one(id, update).await?;
+        if let Some(item) = self.inner.find_one(id).await? {
+            self.reindex(&id, &item);
+        }
+        Ok(())
+    }
+
+    async fn delete_one(&self, id: ObjectId) -> Result<(), RepoError> {
+        self.inner.delete_one(id).await?;
+        self.search_index.remove_document(&id.to_hex());
+        Ok(())
+    }
+}
+
+pub fn indexed<T>(
+    inner: Arc<dyn Repository<T>>,
+    search_index: Arc<SearchIndex>,
+    collection: &'static str,
+    fields: fn(&T) -> Vec<(&'static str, String)>,
+) -> Arc<dyn Repository<T>>
+where
+    T: Send + Sync + 'static,
+{
+    Arc::new(IndexedRepository::new(inner, search_index, collection, fields))
+}
+
+pub fn mongo<T>(collection: Collection<T>) -> Arc<dyn Repository<T>>
+where
+    T: Serialize + DeserializeOwned + Unpin + Send + Sync + 'static,
+{
+    Arc::new(MongoRepository::new(collection))
+}
+
+pub fn in_memory<T>() -> Arc<dyn Repository<T>>
+where
+    T: Identifiable + Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    Arc::new(InMemoryRepository::new())
+}
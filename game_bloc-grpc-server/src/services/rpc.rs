@@ -0,0 +1,160 @@
+use bson::oid::ObjectId;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+use centrium_grpc_server::game_bloc_service_server::GameBlocService;
+use centrium_grpc_server::{
+    GetProfileRequest, GetProfileResponse, NotificationEvent, Profile, SearchHighlight,
+    SearchRequest, SearchResponse, SearchResult, SubscribeNotificationsRequest,
+};
+
+use fluent_bundle::FluentArgs;
+
+use super::auth::{require_scope, Scope};
+use super::db::GameBlocServiceState;
+use super::i18n;
+use super::notifications;
+
+#[tonic::async_trait]
+impl GameBlocService for GameBlocServiceState {
+    async fn get_profile(
+        &self,
+        request: Request<GetProfileRequest>,
+    ) -> Result<Response<GetProfileResponse>, Status> {
+        require_scope(&request, Scope::ProfileRead)?;
+        let locale = self.i18n.negotiate(i18n::accept_language(&request));
+
+        let id = request.into_inner().id;
+        let oid = ObjectId::parse_str(&id).map_err(|_| {
+            let mut args = FluentArgs::new();
+            args.set("id", id.clone());
+            Status::invalid_argument(self.i18n.message(&locale, "invalid-id", Some(&args)))
+        })?;
+
+        let profile_doc = self.profile_store.find_one(oid).await?.ok_or_else(|| {
+            let mut args = FluentArgs::new();
+            args.set("id", id.clone());
+            Status::not_found(self.i18n.message(&locale, "user-not-found", Some(&args)))
+        })?;
+
+        Ok(Response::new(GetProfileResponse {
+            profile: Some(Profile {
+                id,
+                owner_id: profile_doc.owner_id,
+                display_name: profile_doc.display_name,
+            }),
+        }))
+    }
+
+    type SubscribeNotificationsStream = ReceiverStream<Result<NotificationEvent, Status>>;
+
+    async fn subscribe_notifications(
+        &self,
+        request: Request<SubscribeNotificationsRequest>,
+    ) -> Result<Response<Self::SubscribeNotificationsStream>, Status> {
+        require_scope(&request, Scope::ProfileRead)?;
+        let locale = self.i18n.negotiate(i18n::accept_language(&request));
+
+        let req = request.into_inner();
+        let resume_token = if req.resume_token.is_empty() {
+            None
+        } else {
+            Some(
+                serde_json::from_str::<bson::Document>(&req.resume_token)
+                    .map_err(|e| Status::invalid_argument(format!("invalid resume_token: {e}")))?,
+            )
+        };
+
+        let stream =
+            notifications::subscribe(self.clone(), req.owner_id, resume_token, locale).await?;
+
+        Ok(Response::new(stream))
+    }
+
+    async fn search(
+        &self,
+        request: Request<SearchRequest>,
+    ) -> Result<Response<SearchResponse>, Status> {
+        require_scope(&request, Scope::ProfileRead)?;
+
+        let req = request.into_inner();
+        let limit = if req.top_n == 0 { 10 } else { req.top_n as usize };
+
+        let results = self
+            .search_index
+            .search(&req.query, limit)
+            .into_iter()
+            .map(|hit| SearchResult {
+                doc_id: hit.doc_id,
+                collection: hit.collection,
+                score: hit.score,
+                highlights: hit
+                    .highlights
+                    .into_iter()
+                    .map(|h| SearchHighlight {
+                        field: h.field,
+                        start: h.start,
+                        end: h.end,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(Response::new(SearchResponse { results }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rstest::rstest;
+    use tonic::Code;
+
+    use super::*;
+    use crate::models::ProfileDocument;
+    use crate::services::auth::Scopes;
+    use crate::services::db::GameBlocServiceState;
+    use crate::services::repository::{self, Repository};
+
+    #[rstest]
+    #[case::known_id(true, Code::Ok)]
+    #[case::unknown_id(false, Code::NotFound)]
+    #[tokio::test]
+    async fn get_profile_reports_not_found_for_missing_ids(
+        #[case] seed: bool,
+        #[case] expected: Code,
+    ) {
+        let profile_store = repository::in_memory();
+        let seeded_id = profile_store
+            .insert_one(ProfileDocument {
+                id: None,
+                owner_id: "owner-1".into(),
+                display_name: "Ada".into(),
+            })
+            .await
+            .unwrap();
+        let state = GameBlocServiceState::test_state(profile_store).await;
+
+        let requested_id = if seed {
+            seeded_id.to_hex()
+        } else {
+            ObjectId::new().to_hex()
+        };
+
+        let mut request = Request::new(GetProfileRequest { id: requested_id });
+        request
+            .extensions_mut()
+            .insert(Scopes(HashSet::from([Scope::ProfileRead])));
+
+        let result = state.get_profile(request).await;
+
+        match expected {
+            Code::Ok => assert_eq!(
+                result.unwrap().into_inner().profile.unwrap().owner_id,
+                "owner-1"
+            ),
+            code => assert_eq!(result.unwrap_err().code(), code),
+        }
+    }
+}
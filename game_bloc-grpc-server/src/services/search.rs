@@ -0,0 +1,338 @@
+//! Inverted-index full-text search over `profile_store`, `squad_store`
+//! and `games_store`.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Every occurrence of one term in one field of one document.
+#[derive(Debug, Clone)]
+struct Posting {
+    doc_id: String,
+    collection: String,
+    field: String,
+    positions: Vec<usize>,
+    /// Byte `(start, end)` spans of each occurrence in the original
+    /// field text, used to build `Highlight`s. `positions` alone can't
+    /// do this: those are word ordinals, not byte offsets.
+    offsets: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Highlight {
+    pub field: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub doc_id: String,
+    pub collection: String,
+    pub score: f32,
+    pub highlights: Vec<Highlight>,
+}
+
+/// Thread-safe inverted index keyed by normalized term, updated
+/// incrementally as documents are written and queried by `Search`.
+#[derive(Default)]
+pub struct SearchIndex {
+    postings: RwLock<HashMap<String, Vec<Posting>>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// (Re)indexes a single document's fields, replacing any postings
+    /// previously recorded for `doc_id`. Call this after every insert
+    /// or update to the underlying collection.
+    pub fn upsert_document(&self, collection: &str, doc_id: &str, fields: &[(&str, &str)]) {
+        self.remove_document(doc_id);
+
+        let mut postings = self.postings.write().unwrap();
+        for (field, text) in fields {
+            let mut per_term: HashMap<String, (Vec<usize>, Vec<(usize, usize)>)> = HashMap::new();
+            for (position, (term, start, end)) in
+                tokenize_with_offsets(text).into_iter().enumerate()
+            {
+                let entry = per_term.entry(term).or_default();
+                entry.0.push(position);
+                entry.1.push((start, end));
+            }
+            for (term, (positions, offsets)) in per_term {
+                postings.entry(term).or_default().push(Posting {
+                    doc_id: doc_id.to_string(),
+                    collection: collection.to_string(),
+                    field: field.to_string(),
+                    positions,
+                    offsets,
+                });
+            }
+        }
+    }
+
+    /// Drops all postings for `doc_id`, e.g. on delete or before a
+    /// re-index.
+    pub fn remove_document(&self, doc_id: &str) {
+        let mut postings = self.postings.write().unwrap();
+        for list in postings.values_mut() {
+            list.retain(|p| p.doc_id != doc_id);
+        }
+    }
+
+    /// Ranks documents against `query`, returning the top `limit` hits.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let query_terms = tokenize(query);
+        if query_terms.is_empty() {
+            return Vec::new();
+        }
+
+        let postings = self.postings.read().unwrap();
+        let mut by_doc: HashMap<String, (String, f32, Vec<Highlight>, Vec<Vec<usize>>)> =
+            HashMap::new();
+
+        for query_term in &query_terms {
+            for (term, bonus) in matching_terms(&postings, query_term) {
+                for posting in &postings[&term] {
+                    let entry = by_doc
+                        .entry(posting.doc_id.clone())
+                        .or_insert_with(|| (posting.collection.clone(), 0.0, Vec::new(), Vec::new()));
+
+                    entry.1 += 1.0 + bonus;
+                    entry.2.push(Highlight {
+                        field: posting.field.clone(),
+                        start: posting.offsets.first().map(|(s, _)| *s).unwrap_or(0) as u32,
+                        end: posting.offsets.last().map(|(_, e)| *e).unwrap_or(0) as u32,
+                    });
+                    entry.3.push(posting.positions.clone());
+                }
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = by_doc
+            .into_iter()
+            .map(|(doc_id, (collection, term_score, highlights, position_lists))| {
+                let proximity_bonus = proximity_score(&position_lists);
+                SearchHit {
+                    doc_id,
+                    collection,
+                    score: term_score + proximity_bonus,
+                    highlights,
+                }
+            })
+            .collect();
+
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        hits.truncate(limit);
+        hits
+    }
+}
+
+/// Finds index terms matching `query_term` either exactly or within a
+/// typo-tolerance bound that widens with the query term's length, and
+/// the score bonus to award each.
+fn matching_terms<'a>(
+    postings: &'a HashMap<String, Vec<Posting>>,
+    query_term: &str,
+) -> Vec<(String, f32)> {
+    let max_distance = if query_term.chars().count() >= 8 {
+        2
+    } else if query_term.chars().count() >= 4 {
+        1
+    } else {
+        0
+    };
+
+    postings
+        .keys()
+        .filter_map(|term| {
+            if term == query_term {
+                return Some((term.clone(), 0.5));
+            }
+            if max_distance == 0 {
+                return None;
+            }
+            bounded_levenshtein(term, query_term, max_distance)
+                .map(|distance| (term.clone(), 0.25 / (distance as f32 + 1.0)))
+        })
+        .collect()
+}
+
+/// Rewards documents whose matched terms appear close together: the
+/// bonus is `1 / span`, where span runs from the smallest to the largest
+/// position found in any of the position lists (not the narrowest window
+/// holding one position from each list).
+fn proximity_score(position_lists: &[Vec<usize>]) -> f32 {
+    if position_lists.len() < 2 {
+        return 0.0;
+    }
+
+    let min = position_lists.iter().filter_map(|p| p.iter().min()).min();
+    let max = position_lists.iter().filter_map(|p| p.iter().max()).max();
+
+    match (min, max) {
+        (Some(min), Some(max)) => {
+            let span = (max - min) as f32 + 1.0;
+            1.0 / span
+        }
+        _ => 0.0,
+    }
+}
+
+/// Levenshtein distance, short-circuiting once it's clear the result
+/// would exceed `max`. Returns `None` in that case.
+fn bounded_levenshtein(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, &ca) in a.iter().enumerate() {
+        let mut curr = vec![i + 1];
+        let mut row_min = curr[0];
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            let value = (prev[j + 1] + 1)
+                .min(curr[j] + 1)
+                .min(prev[j] + cost);
+            curr.push(value);
+            row_min = row_min.min(value);
+        }
+        if row_min > max {
+            return None;
+        }
+        prev = curr;
+    }
+
+    let distance = prev[b.len()];
+    (distance <= max).then_some(distance)
+}
+
+/// Splits `text` on Unicode word boundaries, pairing each resulting
+/// index term with the byte `(start, end)` span it came from in the
+/// original string — so highlights can point back at real text instead
+/// of word ordinals.
+fn tokenize_with_offsets(text: &str) -> Vec<(String, usize, usize)> {
+    text.unicode_word_indices()
+        .map(|(start, word)| (normalize_word(word), start, start + word.len()))
+        .collect()
+}
+
+/// Lowercases and strips diacritics from a single word for indexing.
+fn normalize_word(word: &str) -> String {
+    word.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Lowercases, strips diacritics and splits on Unicode word boundaries.
+/// Used on the query side, where byte offsets into the query aren't
+/// needed.
+fn tokenize(text: &str) -> Vec<String> {
+    tokenize_with_offsets(text)
+        .into_iter()
+        .map(|(term, _, _)| term)
+        .collect()
+}
+
+fn is_combining_mark(c: char) -> bool {
+    matches!(c as u32, 0x0300..=0x036F)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::identical("kitten", "kitten", 2, Some(0))]
+    #[case::classic_example("kitten", "sitting", 3, Some(3))]
+    #[case::exceeds_bound("kitten", "sitting", 2, None)]
+    #[case::length_diff_exceeds_bound("ab", "abcdef", 1, None)]
+    fn bounded_levenshtein_matches_expected_distance(
+        #[case] a: &str,
+        #[case] b: &str,
+        #[case] max: usize,
+        #[case] expected: Option<usize>,
+    ) {
+        assert_eq!(bounded_levenshtein(a, b, max), expected);
+    }
+
+    #[test]
+    fn tokenize_with_offsets_returns_byte_spans_into_original_text() {
+        let text = "Hello, World!";
+        let tokens = tokenize_with_offsets(text);
+
+        assert_eq!(tokens.len(), 2);
+        let (term, start, end) = &tokens[0];
+        assert_eq!(term, "hello");
+        assert_eq!(&text[*start..*end], "Hello");
+
+        let (term, start, end) = &tokens[1];
+        assert_eq!(term, "world");
+        assert_eq!(&text[*start..*end], "World");
+    }
+
+    #[test]
+    fn tokenize_with_offsets_strips_diacritics_from_the_index_term() {
+        let tokens = tokenize_with_offsets("café");
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].0, "cafe");
+        assert_eq!(&"café"[tokens[0].1..tokens[0].2], "café");
+    }
+
+    #[test]
+    fn search_matches_exact_and_typo_tolerant_queries() {
+        let index = SearchIndex::new();
+        index.upsert_document("tournaments", "doc-1", &[("name", "Tournament")]);
+
+        let exact = index.search("tournament", 10);
+        assert_eq!(exact.len(), 1);
+        assert_eq!(exact[0].doc_id, "doc-1");
+
+        let typo = index.search("tornament", 10);
+        assert_eq!(typo.len(), 1);
+        assert_eq!(typo[0].doc_id, "doc-1");
+
+        let unrelated = index.search("zzzzzzzzzz", 10);
+        assert!(unrelated.is_empty());
+    }
+
+    #[test]
+    fn search_highlights_use_real_byte_offsets_not_word_ordinals() {
+        let index = SearchIndex::new();
+        index.upsert_document("profiles", "doc-1", &[("display_name", "Ada Lovelace")]);
+
+        let hits = index.search("lovelace", 10);
+        let hit = hits.into_iter().find(|h| h.doc_id == "doc-1").expect("doc-1 should match");
+        let highlight = hit
+            .highlights
+            .into_iter()
+            .find(|h| h.field == "display_name")
+            .expect("display_name should be highlighted");
+
+        assert_eq!(
+            &"Ada Lovelace"[highlight.start as usize..highlight.end as usize],
+            "Lovelace"
+        );
+    }
+
+    #[test]
+    fn upsert_document_replaces_previous_postings_for_the_same_doc_id() {
+        let index = SearchIndex::new();
+        index.upsert_document("profiles", "doc-1", &[("display_name", "Original")]);
+        index.upsert_document("profiles", "doc-1", &[("display_name", "Replacement")]);
+
+        assert!(index.search("original", 10).is_empty());
+        assert_eq!(index.search("replacement", 10)[0].doc_id, "doc-1");
+    }
+}